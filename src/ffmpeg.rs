@@ -1,9 +1,6 @@
 use crate::ffprobe::FFProbeInfo;
-use crate::{
-    error::{CommandSpawnError, FFMpegError, IOError},
-    ffprobe::VideoStreamInfo,
-};
-use image::{ImageBuffer, Rgb};
+use crate::error::{CommandSpawnError, FFMpegError, IOError};
+use image::{ImageBuffer, Luma, Rgb, Rgba};
 use regex::Regex;
 use snafu::ResultExt;
 use std::process::{ChildStderr, ChildStdout, Command, Stdio};
@@ -20,7 +17,9 @@ pub struct FFMpegVideo {
     stdout: ChildStdout,
     stderr: BufReader<ChildStderr>,
     info: FFProbeInfo,
-    primary_video_stream_info: VideoStreamInfo,
+    pixel_format: PixelFormat,
+    output_width: u32,
+    output_height: u32,
 }
 
 #[derive(Default)]
@@ -28,6 +27,11 @@ pub struct FFMpegVideoOptions {
     sampling_interval: Option<Duration>,
     ffmpeg_path: Option<PathBuf>,
     ffprobe_path: Option<PathBuf>,
+    hwaccel: Option<HwAccel>,
+    pixel_format: Option<PixelFormat>,
+    scale: Option<(u32, u32)>,
+    scene_threshold: Option<f64>,
+    stream_index: Option<usize>,
 }
 
 impl FFMpegVideoOptions {
@@ -51,41 +55,266 @@ impl FFMpegVideoOptions {
             ..self
         }
     }
+
+    /// Decodes using the given hardware accelerator. Falls back to software decoding if the
+    /// accelerator is unavailable on the current machine.
+    pub fn with_hwaccel(self, hwaccel: HwAccel) -> Self {
+        FFMpegVideoOptions {
+            hwaccel: Some(hwaccel),
+            ..self
+        }
+    }
+
+    /// Decodes frames into the given pixel format. Defaults to `PixelFormat::Rgb24`.
+    pub fn with_pixel_format(self, pixel_format: PixelFormat) -> Self {
+        FFMpegVideoOptions {
+            pixel_format: Some(pixel_format),
+            ..self
+        }
+    }
+
+    /// Scales each decoded frame to `width`x`height` on the ffmpeg side, so full-resolution
+    /// buffers never have to be shipped into Rust.
+    pub fn with_scale(self, width: u32, height: u32) -> Self {
+        FFMpegVideoOptions {
+            scale: Some((width, height)),
+            ..self
+        }
+    }
+
+    /// Emits a frame whenever the scene-difference score crosses `threshold` (typically
+    /// ~0.3-0.4), instead of sampling at a fixed interval. Takes precedence over
+    /// `with_sampling_interval` since the two sampling strategies are mutually exclusive.
+    pub fn with_scene_threshold(self, threshold: f64) -> Self {
+        FFMpegVideoOptions {
+            scene_threshold: Some(threshold),
+            ..self
+        }
+    }
+
+    /// Targets the `index`-th video stream instead of the sole/primary one, for files with
+    /// multiple video streams (e.g. multi-program or attached-thumbnail files).
+    pub fn with_stream_index(self, index: usize) -> Self {
+        FFMpegVideoOptions {
+            stream_index: Some(index),
+            ..self
+        }
+    }
+}
+
+/// The pixel format decoded frames are delivered in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum PixelFormat {
+    #[default]
+    Rgb24,
+    Rgba,
+    Gray8,
+}
+
+impl PixelFormat {
+    fn as_ffmpeg_name(&self) -> &'static str {
+        match self {
+            PixelFormat::Rgb24 => "rgb24",
+            PixelFormat::Rgba => "rgba",
+            PixelFormat::Gray8 => "gray",
+        }
+    }
+
+    fn bytes_per_pixel(&self) -> u32 {
+        match self {
+            PixelFormat::Rgb24 => 3,
+            PixelFormat::Rgba => 4,
+            PixelFormat::Gray8 => 1,
+        }
+    }
+}
+
+/// A hardware acceleration method ffmpeg can use to decode frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HwAccel {
+    Vaapi,
+    Cuda,
+    VideoToolbox,
+    Qsv,
+    Auto,
+}
+
+impl HwAccel {
+    fn as_ffmpeg_name(&self) -> &'static str {
+        match self {
+            HwAccel::Vaapi => "vaapi",
+            HwAccel::Cuda => "cuda",
+            HwAccel::VideoToolbox => "videotoolbox",
+            HwAccel::Qsv => "qsv",
+            HwAccel::Auto => "auto",
+        }
+    }
+}
+
+/// The subset of `FFMpegVideoOptions` that `build_ffmpeg_args` needs, kept as a separate
+/// (all-`Copy`) struct so the argument list can be built and tested without a `Command`.
+struct FFMpegArgs {
+    hwaccel: Option<HwAccel>,
+    pixel_format: PixelFormat,
+    scale: Option<(u32, u32)>,
+    scene_threshold: Option<f64>,
+    sampling_interval: Option<Duration>,
+    stream_index: Option<usize>,
+}
+
+/// Builds the ffmpeg command-line arguments for decoding `video_path` into raw frames.
+fn build_ffmpeg_args(
+    video_path: &Path,
+    seek_start: Option<Duration>,
+    seek_duration: Option<Duration>,
+    frame_limit: Option<u64>,
+    args: &FFMpegArgs,
+) -> Vec<String> {
+    let mut out = Vec::new();
+
+    if let Some(hwaccel) = args.hwaccel {
+        out.push("-hwaccel".to_owned());
+        out.push(hwaccel.as_ffmpeg_name().to_owned());
+        if hwaccel == HwAccel::Vaapi {
+            out.push("-hwaccel_output_format".to_owned());
+            out.push("vaapi".to_owned());
+        }
+    }
+
+    if let Some(seek_start) = seek_start {
+        // Placed before `-i` so ffmpeg seeks via the (fast) demuxer instead of decoding
+        // and discarding every frame up to this point.
+        out.push("-ss".to_owned());
+        out.push(format!("{:.3}", seek_start.as_secs_f64()));
+    }
+
+    out.push("-i".to_owned());
+    out.push(video_path.to_string_lossy().into_owned());
+
+    if let Some(seek_duration) = seek_duration {
+        out.push("-t".to_owned());
+        out.push(format!("{:.3}", seek_duration.as_secs_f64()));
+    }
+
+    // Always explicit, even when `stream_index` is unset, so the stream ffmpeg decodes can
+    // never diverge from the stream `video_stream(index)` is about to size buffers for.
+    out.push("-map".to_owned());
+    out.push(format!("0:v:{}", args.stream_index.unwrap_or(0)));
+
+    let mut filters = Vec::<String>::new();
+
+    if args.hwaccel == Some(HwAccel::Vaapi) {
+        // Brings the frame back into system memory as rgb24 before anything downstream
+        // (e.g. `select`'s scene-difference scoring) needs to read pixel data.
+        filters.push("hwdownload".to_owned());
+        filters.push("format=rgb24".to_owned());
+    }
+
+    if let Some(threshold) = args.scene_threshold {
+        // Frames are no longer evenly spaced; the reader must rely on each frame's
+        // `pts_time` alone to report its `time_offset`.
+        filters.push(format!("select='gt(scene,{})'", threshold));
+    } else if let Some(interval) = args.sampling_interval {
+        filters.push(format!("fps=1/{:?}", interval.as_secs()));
+    }
+
+    if let Some((width, height)) = args.scale {
+        filters.push(format!("scale={}:{}", width, height));
+    }
+
+    filters.push("showinfo".to_owned());
+
+    out.push("-vf".to_owned());
+    out.push(filters.join(","));
+
+    if let Some(frame_limit) = frame_limit {
+        // Bounds the encode to at most this many frames so ffmpeg stops decoding right
+        // after the seek point instead of churning through the rest of the file.
+        out.push("-frames:v".to_owned());
+        out.push(frame_limit.to_string());
+    }
+
+    out.push("-f".to_owned());
+    out.push("image2pipe".to_owned());
+    out.push("-an".to_owned()); // disable audio processing
+    out.push("-sn".to_owned()); // disable sub-title processing
+    out.push("-pix_fmt".to_owned());
+    out.push(args.pixel_format.as_ffmpeg_name().to_owned());
+    out.push("-nostats".to_owned());
+    out.push("-vcodec".to_owned());
+    out.push("rawvideo".to_owned());
+    out.push("-".to_owned());
+
+    out
 }
 
 impl FFMpegVideo {
     pub fn open(video_path: &Path, options: FFMpegVideoOptions) -> Result<Self, FFMpegError> {
+        Self::open_seeked(video_path, None, None, None, options)
+    }
+
+    /// Seeks to `at` and returns the single frame nearest to that timestamp.
+    ///
+    /// The seek is performed by ffmpeg itself (before the input is opened), so it snaps to
+    /// the nearest decodable keyframe instead of decoding every preceding frame. The encode is
+    /// additionally bounded to a single frame, so ffmpeg doesn't keep decoding the rest of the
+    /// file after the seek point. The reported `time_offset` of the returned frame still
+    /// reflects the real decoded position.
+    pub fn frame_at(
+        video_path: &Path,
+        at: Duration,
+        options: FFMpegVideoOptions,
+    ) -> Result<Frame, FFMpegError> {
+        let mut video = Self::open_seeked(video_path, Some(at), None, Some(1), options)?;
+        video.next().ok_or(FFMpegError::ParseError)?
+    }
+
+    /// Seeks to `start` and decodes frames until `end` is reached.
+    pub fn frames_between(
+        video_path: &Path,
+        start: Duration,
+        end: Duration,
+        options: FFMpegVideoOptions,
+    ) -> Result<Self, FFMpegError> {
+        Self::open_seeked(
+            video_path,
+            Some(start),
+            Some(end.saturating_sub(start)),
+            None,
+            options,
+        )
+    }
+
+    fn open_seeked(
+        video_path: &Path,
+        seek_start: Option<Duration>,
+        seek_duration: Option<Duration>,
+        frame_limit: Option<u64>,
+        options: FFMpegVideoOptions,
+    ) -> Result<Self, FFMpegError> {
         let info = FFProbeInfo::of(video_path, options.ffprobe_path)?;
 
+        let pixel_format = options.pixel_format.unwrap_or_default();
+
         let mut cmd = Command::new(
             options
                 .ffmpeg_path
                 .map_or("ffmpeg".to_owned(), |p| p.to_string_lossy().into()),
         );
-        cmd.args(&["-i", &video_path.to_string_lossy()]);
-
-        let mut filters = Vec::<String>::new();
-
-        if let Some(interval) = options.sampling_interval {
-            filters.push(format!("fps=1/{:?}", interval.as_secs()));
-        }
-
-        filters.push("showinfo".to_owned());
-
-        cmd.args(&["-vf", &filters.join(",")]);
-
-        cmd.args(&[
-            "-f",
-            "image2pipe",
-            "-an", // disable audio processing
-            "-sn", // disable sub-title processing
-            "-pix_fmt",
-            "rgb24",
-            "-nostats",
-            "-vcodec",
-            "rawvideo",
-            "-",
-        ]);
+        cmd.args(build_ffmpeg_args(
+            video_path,
+            seek_start,
+            seek_duration,
+            frame_limit,
+            &FFMpegArgs {
+                hwaccel: options.hwaccel,
+                pixel_format,
+                scale: options.scale,
+                scene_threshold: options.scene_threshold,
+                sampling_interval: options.sampling_interval,
+                stream_index: options.stream_index,
+            },
+        ));
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
@@ -94,16 +323,28 @@ impl FFMpegVideo {
         let stdout = child.stdout.unwrap();
         let stderr = child.stderr.unwrap();
 
-        let primary_video_stream_info = info
-            .primary_video_stream()
-            .ok_or(FFMpegError::ParseError)?
-            .clone();
+        let stream_info = match options.stream_index {
+            Some(index) => info.video_stream(index).ok_or(FFMpegError::ParseError)?.clone(),
+            // `primary_video_stream` is only `Some` for single-video-stream files; fall back
+            // to the first video stream so multi-stream files still open by default.
+            None => info
+                .primary_video_stream()
+                .or_else(|| info.video_stream(0))
+                .ok_or(FFMpegError::ParseError)?
+                .clone(),
+        };
+
+        let (output_width, output_height) = options
+            .scale
+            .unwrap_or((stream_info.width, stream_info.height));
 
         Ok(FFMpegVideo {
             stdout,
             stderr: BufReader::new(stderr),
             info,
-            primary_video_stream_info,
+            pixel_format,
+            output_width,
+            output_height,
         })
     }
 
@@ -112,15 +353,24 @@ impl FFMpegVideo {
     }
 }
 
-pub struct Frame {
+/// A decoded frame, carrying its image in whichever pixel format it was decoded with.
+pub enum Frame {
+    Rgb(FrameData<RgbFrameBuffer>),
+    Rgba(FrameData<RgbaFrameBuffer>),
+    Gray(FrameData<GrayFrameBuffer>),
+}
+
+pub struct FrameData<I> {
     /// The decoded image.
-    pub image: FrameBuffer,
+    pub image: I,
 
     /// The offset of this frame in the video. Might not be the true time offset.
     pub time_offset: Duration,
 }
 
-pub type FrameBuffer = ImageBuffer<Rgb<u8>, Vec<u8>>;
+pub type RgbFrameBuffer = ImageBuffer<Rgb<u8>, Vec<u8>>;
+pub type RgbaFrameBuffer = ImageBuffer<Rgba<u8>, Vec<u8>>;
+pub type GrayFrameBuffer = ImageBuffer<Luma<u8>, Vec<u8>>;
 
 impl FFMpegVideo {
     fn get_next(&mut self) -> Result<Option<Frame>, FFMpegError> {
@@ -142,8 +392,9 @@ impl FFMpegVideo {
         }
         let time_seconds = f64::from_str(infos.get("pts_time").unwrap()).unwrap();
 
-        let i = &self.primary_video_stream_info;
-        let mut buffer = vec![0u8; (i.width * i.height * 3) as usize];
+        let byte_size =
+            (self.output_width * self.output_height * self.pixel_format.bytes_per_pixel()) as usize;
+        let mut buffer = vec![0u8; byte_size];
 
         if let Err(err) = self.stdout.read_exact(&mut buffer) {
             if err.kind() == ErrorKind::UnexpectedEof {
@@ -153,17 +404,27 @@ impl FFMpegVideo {
             return Err(FFMpegError::IOError { source: err });
         }
 
-        let image = FrameBuffer::from_raw(
-            self.primary_video_stream_info.width,
-            self.primary_video_stream_info.height,
-            buffer,
-        )
-        .expect("Buffer to have correct size");
-
-        Ok(Some(Frame {
-            image,
-            time_offset: Duration::from_secs_f64(time_seconds),
-        }))
+        let time_offset = Duration::from_secs_f64(time_seconds);
+
+        let frame = match self.pixel_format {
+            PixelFormat::Rgb24 => Frame::Rgb(FrameData {
+                image: RgbFrameBuffer::from_raw(self.output_width, self.output_height, buffer)
+                    .expect("Buffer to have correct size"),
+                time_offset,
+            }),
+            PixelFormat::Rgba => Frame::Rgba(FrameData {
+                image: RgbaFrameBuffer::from_raw(self.output_width, self.output_height, buffer)
+                    .expect("Buffer to have correct size"),
+                time_offset,
+            }),
+            PixelFormat::Gray8 => Frame::Gray(FrameData {
+                image: GrayFrameBuffer::from_raw(self.output_width, self.output_height, buffer)
+                    .expect("Buffer to have correct size"),
+                time_offset,
+            }),
+        };
+
+        Ok(Some(frame))
     }
 }
 
@@ -293,4 +554,192 @@ mod tests {
             @"null"
         );
     }
+
+    use super::{build_ffmpeg_args, FFMpegArgs};
+    use std::path::Path;
+    use std::time::Duration;
+
+    fn default_args() -> FFMpegArgs {
+        FFMpegArgs {
+            hwaccel: None,
+            pixel_format: super::PixelFormat::Rgb24,
+            scale: None,
+            scene_threshold: None,
+            sampling_interval: None,
+            stream_index: None,
+        }
+    }
+
+    #[test]
+    fn build_ffmpeg_args_seeks_before_input_and_bounds_duration_after_it() {
+        let args = build_ffmpeg_args(
+            Path::new("video.mp4"),
+            Some(Duration::from_secs_f64(12.5)),
+            Some(Duration::from_secs_f64(3.0)),
+            None,
+            &default_args(),
+        );
+
+        let i_pos = args.iter().position(|a| a == "-i").unwrap();
+        let ss_pos = args.iter().position(|a| a == "-ss").unwrap();
+        let t_pos = args.iter().position(|a| a == "-t").unwrap();
+
+        assert!(ss_pos < i_pos, "-ss must come before -i");
+        assert!(t_pos > i_pos, "-t must come after -i");
+        assert_eq!(args[ss_pos + 1], "12.500");
+        assert_eq!(args[t_pos + 1], "3.000");
+    }
+
+    #[test]
+    fn build_ffmpeg_args_omits_seek_and_duration_when_unset() {
+        let args = build_ffmpeg_args(Path::new("video.mp4"), None, None, None, &default_args());
+
+        assert!(!args.contains(&"-ss".to_owned()));
+        assert!(!args.contains(&"-t".to_owned()));
+    }
+
+    #[test]
+    fn build_ffmpeg_args_bounds_frame_count_for_single_frame_extraction() {
+        let args = build_ffmpeg_args(
+            Path::new("video.mp4"),
+            Some(Duration::from_secs(1)),
+            None,
+            Some(1),
+            &default_args(),
+        );
+
+        let pos = args.iter().position(|a| a == "-frames:v").unwrap();
+        assert_eq!(args[pos + 1], "1");
+    }
+
+    #[test]
+    fn build_ffmpeg_args_always_maps_a_video_stream_even_without_stream_index() {
+        let args = build_ffmpeg_args(Path::new("video.mp4"), None, None, None, &default_args());
+
+        let pos = args.iter().position(|a| a == "-map").unwrap();
+        assert_eq!(args[pos + 1], "0:v:0");
+    }
+
+    #[test]
+    fn build_ffmpeg_args_sets_pix_fmt_from_options() {
+        let args = build_ffmpeg_args(
+            Path::new("video.mp4"),
+            None,
+            None,
+            None,
+            &FFMpegArgs {
+                pixel_format: super::PixelFormat::Gray8,
+                ..default_args()
+            },
+        );
+
+        let pos = args.iter().position(|a| a == "-pix_fmt").unwrap();
+        assert_eq!(args[pos + 1], "gray");
+    }
+
+    #[test]
+    fn build_ffmpeg_args_adds_scale_filter_when_set() {
+        let args = build_ffmpeg_args(
+            Path::new("video.mp4"),
+            None,
+            None,
+            None,
+            &FFMpegArgs {
+                scale: Some((320, 240)),
+                ..default_args()
+            },
+        );
+
+        let vf = &args[args.iter().position(|a| a == "-vf").unwrap() + 1];
+        assert!(vf.contains("scale=320:240"));
+    }
+
+    #[test]
+    fn build_ffmpeg_args_omits_scale_filter_when_unset() {
+        let args = build_ffmpeg_args(Path::new("video.mp4"), None, None, None, &default_args());
+
+        let vf = &args[args.iter().position(|a| a == "-vf").unwrap() + 1];
+        assert!(!vf.contains("scale="));
+    }
+
+    #[test]
+    fn build_ffmpeg_args_uses_fps_filter_for_fixed_sampling_interval() {
+        let args = build_ffmpeg_args(
+            Path::new("video.mp4"),
+            None,
+            None,
+            None,
+            &FFMpegArgs {
+                sampling_interval: Some(Duration::from_secs(2)),
+                ..default_args()
+            },
+        );
+
+        let vf = &args[args.iter().position(|a| a == "-vf").unwrap() + 1];
+        assert!(vf.contains("fps=1/2"));
+        assert!(!vf.contains("select="));
+    }
+
+    #[test]
+    fn build_ffmpeg_args_scene_threshold_takes_precedence_over_sampling_interval() {
+        let args = build_ffmpeg_args(
+            Path::new("video.mp4"),
+            None,
+            None,
+            None,
+            &FFMpegArgs {
+                scene_threshold: Some(0.4),
+                sampling_interval: Some(Duration::from_secs(2)),
+                ..default_args()
+            },
+        );
+
+        let vf = &args[args.iter().position(|a| a == "-vf").unwrap() + 1];
+        assert!(vf.contains("select='gt(scene,0.4)'"));
+        assert!(!vf.contains("fps="));
+    }
+
+    #[test]
+    fn build_ffmpeg_args_emits_hwaccel_name() {
+        let args = build_ffmpeg_args(
+            Path::new("video.mp4"),
+            None,
+            None,
+            None,
+            &FFMpegArgs {
+                hwaccel: Some(super::HwAccel::Cuda),
+                ..default_args()
+            },
+        );
+
+        let pos = args.iter().position(|a| a == "-hwaccel").unwrap();
+        assert_eq!(args[pos + 1], "cuda");
+        assert!(!args.contains(&"-hwaccel_output_format".to_owned()));
+    }
+
+    #[test]
+    fn build_ffmpeg_args_downloads_vaapi_frames_before_any_other_filter() {
+        let args = build_ffmpeg_args(
+            Path::new("video.mp4"),
+            None,
+            None,
+            None,
+            &FFMpegArgs {
+                hwaccel: Some(super::HwAccel::Vaapi),
+                scene_threshold: Some(0.4),
+                ..default_args()
+            },
+        );
+
+        let pos = args.iter().position(|a| a == "-hwaccel_output_format").unwrap();
+        assert_eq!(args[pos + 1], "vaapi");
+
+        let vf = &args[args.iter().position(|a| a == "-vf").unwrap() + 1];
+        let hwdownload_pos = vf.find("hwdownload").unwrap();
+        let select_pos = vf.find("select=").unwrap();
+        assert!(
+            hwdownload_pos < select_pos,
+            "hwdownload must run before scene-difference scoring reads pixel data"
+        );
+    }
 }
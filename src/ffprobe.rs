@@ -1,5 +1,5 @@
 use crate::error::{CommandSpawnError, FFMpegError, IOError};
-use crate::utils::{fractional_from_str, from_str};
+use crate::utils::{from_str, from_str_option, rational_from_str, Rational};
 use io::Read;
 use serde::Deserialize;
 use snafu::ResultExt;
@@ -15,11 +15,13 @@ use std::{
 pub struct FFProbeInfo {
     pub duration: Duration,
     streams: Vec<StreamInfo>,
+    tags: HashMap<String, String>,
+    chapters: Vec<Chapter>,
 }
 
 enum StreamInfo {
     Video(VideoStreamInfo),
-    // TODO to be extended with AudioStreamInfo
+    Audio(AudioStreamInfo),
 }
 
 #[derive(Clone, Debug)]
@@ -31,11 +33,47 @@ pub struct VideoStreamInfo {
     pub height: u32,
 
     // The frame rate of this stream.
-    pub frame_rate: f64,
+    pub frame_rate: Rational,
+
+    /// The time base this stream's timestamps are expressed in.
+    pub time_base: Rational,
 
     /// The total count of frames in this stream as set in the metadata.
     /// The actual count of frames that can be read might differ.
     pub frames_count: u64,
+
+    /// The name of the codec used to encode this stream, e.g. "h264".
+    pub codec_name: String,
+
+    /// The pixel format frames of this stream are encoded in, e.g. "yuv420p".
+    pub pix_fmt: String,
+}
+
+/// A chapter marker read from the container's metadata.
+#[derive(Clone, Debug)]
+pub struct Chapter {
+    pub id: i64,
+    pub start_time: Duration,
+    pub end_time: Duration,
+    pub title: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AudioStreamInfo {
+    /// The name of the codec used to encode this stream, e.g. "aac".
+    pub codec_name: String,
+
+    /// The sample rate of this stream, in Hz.
+    pub sample_rate: u32,
+
+    /// The number of audio channels.
+    pub channels: u32,
+
+    /// The layout of the channels, e.g. "stereo".
+    pub channel_layout: String,
+
+    /// The bit rate of this stream, in bits per second.
+    pub bit_rate: u64,
 }
 
 impl FFProbeInfo {
@@ -44,28 +82,63 @@ impl FFProbeInfo {
         ffprobe_path: Option<PathBuf>,
     ) -> Result<FFProbeInfo, FFMpegError> {
         let output = FFProbeOutput::of(input_video_path, ffprobe_path)?;
-        Ok(FFProbeInfo {
+        Ok(FFProbeInfo::from_output(output))
+    }
+
+    fn from_output(output: FFProbeOutput) -> FFProbeInfo {
+        FFProbeInfo {
             duration: Duration::from_secs_f64(output.format.duration),
             streams: output
                 .streams
                 .iter()
-                .filter(|s| s.width.is_some() && s.height.is_some())
-                .map(|s| {
-                    StreamInfo::Video(VideoStreamInfo {
-                        width: s.width.unwrap(),
-                        height: s.height.unwrap(),
+                .filter_map(|s| match s.codec_type.as_str() {
+                    "video" => Some(StreamInfo::Video(VideoStreamInfo {
+                        width: s.width?,
+                        height: s.height?,
                         frame_rate: s.avg_frame_rate,
+                        time_base: s.time_base,
                         frames_count: s.nb_frames,
-                    })
+                        codec_name: s.codec_name.clone(),
+                        pix_fmt: s.pix_fmt.clone()?,
+                    })),
+                    "audio" => Some(StreamInfo::Audio(AudioStreamInfo {
+                        codec_name: s.codec_name.clone(),
+                        sample_rate: s.sample_rate?,
+                        channels: s.channels?,
+                        channel_layout: s.channel_layout.clone()?,
+                        bit_rate: s.bit_rate?,
+                    })),
+                    _ => None,
                 })
                 .collect(),
-        })
+            tags: output.format.tags.clone(),
+            chapters: output
+                .chapters
+                .iter()
+                .map(|c| Chapter {
+                    id: c.id,
+                    start_time: Duration::from_secs_f64(c.start_time),
+                    end_time: Duration::from_secs_f64(c.end_time),
+                    title: c.tags.get("title").cloned(),
+                })
+                .collect(),
+        }
     }
 
     pub fn duration(&self) -> Duration {
         self.duration
     }
 
+    /// The container-level metadata tags, e.g. "title" or "creation_time".
+    pub fn tags(&self) -> &HashMap<String, String> {
+        &self.tags
+    }
+
+    /// The chapter markers read from the container's metadata.
+    pub fn chapters(&self) -> &[Chapter] {
+        &self.chapters
+    }
+
     #[allow(unreachable_patterns)]
     pub fn primary_video_stream(&self) -> Option<&VideoStreamInfo> {
         let video_streams = self
@@ -83,12 +156,44 @@ impl FFProbeInfo {
             None
         }
     }
+
+    /// Returns the `index`-th video stream, for files with more than one video track.
+    #[allow(unreachable_patterns)]
+    pub fn video_stream(&self, index: usize) -> Option<&VideoStreamInfo> {
+        self.streams
+            .iter()
+            .filter_map(|s| match s {
+                StreamInfo::Video(v) => Some(v),
+                _ => None,
+            })
+            .nth(index)
+    }
+
+    #[allow(unreachable_patterns)]
+    pub fn primary_audio_stream(&self) -> Option<&AudioStreamInfo> {
+        let audio_streams = self
+            .streams
+            .iter()
+            .filter_map(|s| match s {
+                StreamInfo::Audio(a) => Some(a),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        if audio_streams.len() == 1 {
+            audio_streams.first().cloned()
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Deserialize, Debug)]
 struct FFProbeOutput {
     streams: Vec<FFProbeStreamInfo>,
     format: FFProbeFormat,
+    #[serde(default)]
+    chapters: Vec<FFProbeChapterInfo>,
 }
 
 impl FFProbeOutput {
@@ -111,6 +216,7 @@ impl FFProbeOutput {
                     "stream",
                     "-show_entries",
                     "format",
+                    "-show_chapters",
                     "-of",
                     "json",
                     &input_video_path.to_string_lossy(),
@@ -140,18 +246,129 @@ struct FFProbeStreamInfo {
 
     width: Option<u32>,
     height: Option<u32>,
+    pix_fmt: Option<String>,
 
-    #[serde(deserialize_with = "fractional_from_str")]
-    r_frame_rate: f64,
-    #[serde(deserialize_with = "fractional_from_str")]
-    avg_frame_rate: f64,
+    #[serde(deserialize_with = "rational_from_str")]
+    r_frame_rate: Rational,
+    #[serde(deserialize_with = "rational_from_str")]
+    avg_frame_rate: Rational,
+    #[serde(deserialize_with = "rational_from_str")]
+    time_base: Rational,
     #[serde(deserialize_with = "from_str")]
     nb_frames: u64,
+
+    #[serde(default, deserialize_with = "from_str_option")]
+    sample_rate: Option<u32>,
+    channels: Option<u32>,
+    channel_layout: Option<String>,
+    #[serde(default, deserialize_with = "from_str_option")]
+    bit_rate: Option<u64>,
 }
 
 #[derive(Deserialize, Debug)]
 struct FFProbeFormat {
     #[serde(deserialize_with = "from_str")]
     duration: f64,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct FFProbeChapterInfo {
+    id: i64,
+    #[serde(deserialize_with = "from_str")]
+    start_time: f64,
+    #[serde(deserialize_with = "from_str")]
+    end_time: f64,
+    #[serde(default)]
     tags: HashMap<String, String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::FFProbeInfo;
+
+    #[test]
+    fn from_output_splits_video_and_audio_streams_and_defaults_untagged_metadata() {
+        let output = serde_json::from_str(
+            r#"{
+                "streams": [
+                    {
+                        "codec_name": "h264",
+                        "codec_type": "video",
+                        "width": 1920,
+                        "height": 1080,
+                        "pix_fmt": "yuv420p",
+                        "r_frame_rate": "30000/1001",
+                        "avg_frame_rate": "30000/1001",
+                        "time_base": "1/90000",
+                        "nb_frames": "180"
+                    },
+                    {
+                        "codec_name": "aac",
+                        "codec_type": "audio",
+                        "r_frame_rate": "0/0",
+                        "avg_frame_rate": "0/0",
+                        "time_base": "1/48000",
+                        "nb_frames": "0",
+                        "sample_rate": "48000",
+                        "channels": 2,
+                        "channel_layout": "stereo",
+                        "bit_rate": "128000"
+                    }
+                ],
+                "format": {
+                    "duration": "6.0"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let info = FFProbeInfo::from_output(output);
+
+        let video = info.primary_video_stream().unwrap();
+        assert_eq!(video.width, 1920);
+        assert_eq!(video.height, 1080);
+        assert_eq!(video.codec_name, "h264");
+        assert_eq!(video.pix_fmt, "yuv420p");
+
+        let audio = info.primary_audio_stream().unwrap();
+        assert_eq!(audio.codec_name, "aac");
+        assert_eq!(audio.sample_rate, 48000);
+        assert_eq!(audio.channels, 2);
+        assert_eq!(audio.channel_layout, "stereo");
+        assert_eq!(audio.bit_rate, 128000);
+
+        assert!(info.tags().is_empty());
+        assert!(info.chapters().is_empty());
+    }
+
+    #[test]
+    fn from_output_parses_chapters_and_format_tags() {
+        let output = serde_json::from_str(
+            r#"{
+                "streams": [],
+                "format": {
+                    "duration": "10.0",
+                    "tags": { "title": "My Movie" }
+                },
+                "chapters": [
+                    { "id": 0, "start_time": "0.0", "end_time": "5.0", "tags": { "title": "Intro" } },
+                    { "id": 1, "start_time": "5.0", "end_time": "10.0", "tags": {} }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let info = FFProbeInfo::from_output(output);
+
+        assert_eq!(info.tags().get("title").map(String::as_str), Some("My Movie"));
+
+        let chapters = info.chapters();
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].id, 0);
+        assert_eq!(chapters[0].title.as_deref(), Some("Intro"));
+        assert_eq!(chapters[1].id, 1);
+        assert_eq!(chapters[1].title, None);
+    }
+}
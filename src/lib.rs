@@ -7,5 +7,9 @@ mod utils;
 extern crate lazy_static;
 
 pub use error::FFMpegError;
-pub use ffmpeg::{FFMpegVideo, FFMpegVideoOptions};
-pub use ffprobe::{FFProbeInfo, VideoStreamInfo};
+pub use ffmpeg::{
+    FFMpegVideo, FFMpegVideoOptions, Frame, FrameData, GrayFrameBuffer, HwAccel, PixelFormat,
+    RgbFrameBuffer, RgbaFrameBuffer,
+};
+pub use ffprobe::{AudioStreamInfo, Chapter, FFProbeInfo, VideoStreamInfo};
+pub use utils::Rational;
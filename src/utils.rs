@@ -13,19 +13,93 @@ where
     T::from_str(&s).map_err(de::Error::custom)
 }
 
-pub fn fractional_from_str<'de, D>(deserializer: D) -> Result<f64, D::Error>
+pub fn from_str_option<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: FromStr,
+    T::Err: Display,
+    D: Deserializer<'de>,
+{
+    let s: Option<String> = Option::deserialize(deserializer)?;
+    match s {
+        Some(s) => T::from_str(&s).map(Some).map_err(de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// An exact fraction, e.g. ffprobe's `r_frame_rate` of `30000/1001`.
+///
+/// Keeping the numerator and denominator intact (rather than collapsing them into an `f64`)
+/// lets consumers compute frame indices and PTS exactly, without accumulating rounding error
+/// over a long stream.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rational {
+    pub num: i64,
+    pub den: i64,
+}
+
+impl Rational {
+    pub fn as_f64(&self) -> f64 {
+        self.num as f64 / self.den as f64
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.num, self.den)
+    }
+}
+
+impl FromStr for Rational {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split('/').collect();
+
+        if parts.len() != 2 {
+            return Err("Cannot parse fraction".to_owned());
+        }
+
+        let num = i64::from_str(parts[0]).map_err(|e| e.to_string())?;
+        let den = i64::from_str(parts[1]).map_err(|e| e.to_string())?;
+
+        Ok(Rational { num, den })
+    }
+}
+
+pub fn rational_from_str<'de, D>(deserializer: D) -> Result<Rational, D::Error>
 where
     D: Deserializer<'de>,
 {
     let s = String::deserialize(deserializer)?;
-    let s: Vec<&str> = s.split('/').collect();
+    Rational::from_str(&s).map_err(de::Error::custom)
+}
 
-    if s.len() != 2 {
-        return Err(de::Error::custom("Cannot parse fraction".to_owned()));
+#[cfg(test)]
+mod tests {
+    use super::Rational;
+    use std::str::FromStr;
+
+    #[test]
+    fn rational_from_str_parses_numerator_and_denominator() {
+        let r = Rational::from_str("30000/1001").unwrap();
+        assert_eq!(r.num, 30000);
+        assert_eq!(r.den, 1001);
     }
 
-    let numerator = f64::from_str(s[0]).map_err(de::Error::custom)?;
-    let denominator = f64::from_str(s[1]).map_err(de::Error::custom)?;
+    #[test]
+    fn rational_from_str_rejects_non_fractions() {
+        assert!(Rational::from_str("30000").is_err());
+    }
 
-    return Ok(numerator / denominator);
+    #[test]
+    fn rational_display_round_trips_through_from_str() {
+        let r = Rational::from_str("24/1").unwrap();
+        assert_eq!(Rational::from_str(&r.to_string()).unwrap(), r);
+    }
+
+    #[test]
+    fn rational_as_f64_divides_numerator_by_denominator() {
+        let r = Rational { num: 30000, den: 1001 };
+        assert!((r.as_f64() - 29.97002997).abs() < 1e-6);
+    }
 }